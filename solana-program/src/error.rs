@@ -0,0 +1,31 @@
+use solana_program::program_error::ProgramError;
+use thiserror::Error;
+
+/// Errors specific to the delegation program.
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+pub enum DelegationError {
+    #[error("delegation account is not initialized")]
+    NotInitialized,
+    #[error("delegation has expired")]
+    Expired,
+    #[error("requested amount exceeds the delegation's overall allowance")]
+    AmountExceedsAllowance,
+    #[error("operation type is not permitted by this delegation")]
+    OperationNotPermitted,
+    #[error("operation type does not fit in the permissions bitmap")]
+    InvalidOperationType,
+    #[error("no spending rule configured for this operation type")]
+    NoRuleForOperation,
+    #[error("operation exceeds its sliding-window rate limit")]
+    RateLimitExceeded,
+    #[error("execute_delegated only supports CPIing an SPL token Transfer or TransferChecked")]
+    UnsupportedTarget,
+    #[error("inner instruction's token amount does not match the verified amount")]
+    AmountMismatch,
+}
+
+impl From<DelegationError> for ProgramError {
+    fn from(e: DelegationError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,32 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::state::OperationRule;
+
+// Instructions the program will handle
+#[derive(Debug, BorshSerialize, BorshDeserialize)]
+pub enum DelegationInstruction {
+    // Create a new delegation, with one sliding-window spending rule per
+    // operation type it should be allowed to perform. `None` means "no
+    // expiry" / "no overall cap" rather than overloading a sentinel value.
+    Create {
+        expiry_timestamp: Option<i64>,
+        max_amount: Option<u64>,
+        permissions: u32,
+        rules: Vec<OperationRule>,
+    },
+    // Revoke an existing delegation
+    Revoke,
+    // Verify a transaction is within delegation parameters
+    VerifyTransaction { amount: u64, operation_type: u32 },
+    // Verify a delegated transaction and then CPI into the target program
+    // with `inner_ix` as its instruction data, using the remaining accounts
+    // passed after the delegation's own accounts.
+    ExecuteDelegated {
+        operation_type: u32,
+        amount: u64,
+        inner_ix: Vec<u8>,
+    },
+    // Read-only query: writes a `state::UsageInfo` to return data so owners
+    // and off-chain monitors can audit how much of a delegation is left.
+    GetUsage,
+}
@@ -1,19 +1,26 @@
-// State structure for delegation account
-pub struct DelegationState {
-    pub initialized: bool,
-    pub owner: Pubkey, // User's wallet
-    pub delegate: Pubkey, // Bot's wallet
-    pub expiry_timestamp: i64,
-    pub max_allowed_amount: u64, // Max amount that can be used
-    pub permissions: u32, // Bitmap of allowed operations
-}
+// `entrypoint!` expands to code referencing `feature = "custom-heap"` / "custom-panic"
+// and `target_os = "solana"`, none of which this crate's Cargo.toml declares; that's
+// solana_program's own macro, not a mistake in this crate, so silence the lint.
+#![allow(unexpected_cfgs)]
+
+use solana_program::{
+    account_info::AccountInfo, entrypoint, entrypoint::ProgramResult, pubkey::Pubkey,
+};
+
+pub mod error;
+pub mod instruction;
+pub mod processor;
+pub mod state;
+pub mod validation;
 
-// Instructions the program will handle
-pub enum DelegationInstruction {
-    // Create a new delegation
-    Create { expiry_timestamp: i64, max_amount: u64, permissions: u32 },
-    // Revoke an existing delegation
-    Revoke,
-    // Verify a transaction is within delegation parameters
-    VerifyTransaction { amount: u64, operation_type: u32 },
-}
\ No newline at end of file
+use processor::Processor;
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    Processor::process(program_id, accounts, instruction_data)
+}
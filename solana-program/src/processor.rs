@@ -0,0 +1,243 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use bytemuck::Zeroable;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    program::{invoke, set_return_data},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use spl_token::instruction::TokenInstruction;
+
+use crate::{
+    error::DelegationError,
+    instruction::DelegationInstruction,
+    state::{DelegationState, OperationRule, UsageInfo, MAX_OPERATION_RULES},
+    validation::assert_valid_delegation,
+};
+
+pub struct Processor;
+
+impl Processor {
+    pub fn process(_program_id: &Pubkey, accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+        let instruction = DelegationInstruction::try_from_slice(instruction_data)
+            .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+        match instruction {
+            DelegationInstruction::Create { expiry_timestamp, max_amount, permissions, rules } => {
+                Self::process_create(accounts, expiry_timestamp, max_amount, permissions, rules)
+            }
+            DelegationInstruction::Revoke => Self::process_revoke(accounts),
+            DelegationInstruction::VerifyTransaction { amount, operation_type } => {
+                Self::process_verify_transaction(accounts, amount, operation_type)
+            }
+            DelegationInstruction::ExecuteDelegated { operation_type, amount, inner_ix } => {
+                Self::process_execute_delegated(accounts, operation_type, amount, inner_ix)
+            }
+            DelegationInstruction::GetUsage => Self::process_get_usage(accounts),
+        }
+    }
+
+    fn process_create(
+        accounts: &[AccountInfo],
+        expiry_timestamp: Option<i64>,
+        max_amount: Option<u64>,
+        permissions: u32,
+        rules: Vec<OperationRule>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner_info = next_account_info(account_info_iter)?;
+        let delegate_info = next_account_info(account_info_iter)?;
+        let delegation_account_info = next_account_info(account_info_iter)?;
+
+        if !owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if rules.len() > MAX_OPERATION_RULES {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let mut delegation_data = delegation_account_info.data.borrow_mut();
+        let state = DelegationState::load_mut(&mut delegation_data)?;
+        *state = DelegationState::zeroed();
+        state.initialized = 1;
+        state.owner = *owner_info.key;
+        state.delegate = *delegate_info.key;
+        state.expiry_timestamp = expiry_timestamp.into();
+        state.max_allowed_amount = max_amount.into();
+        state.permissions = permissions;
+        state.num_rules = rules.len() as u32;
+        for (slot, rule) in state.rules.iter_mut().zip(rules) {
+            *slot = rule;
+        }
+        Ok(())
+    }
+
+    fn process_revoke(accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner_info = next_account_info(account_info_iter)?;
+        let delegation_account_info = next_account_info(account_info_iter)?;
+
+        if !owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut delegation_data = delegation_account_info.data.borrow_mut();
+        let state = DelegationState::load_mut(&mut delegation_data)?;
+        if state.owner != *owner_info.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        state.initialized = 0;
+        Ok(())
+    }
+
+    // Expected accounts: [owner, delegate, delegation_account, clock, owner_token_account, delegate_token_account, mint]
+    fn process_verify_transaction(accounts: &[AccountInfo], amount: u64, operation_type: u32) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner_info = next_account_info(account_info_iter)?;
+        let delegate_info = next_account_info(account_info_iter)?;
+        let delegation_account_info = next_account_info(account_info_iter)?;
+        let clock_info = next_account_info(account_info_iter)?;
+        let owner_token_account_info = next_account_info(account_info_iter)?;
+        let delegate_token_account_info = next_account_info(account_info_iter)?;
+        let mint_info = next_account_info(account_info_iter)?;
+
+        if !delegate_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let clock = Clock::from_account_info(clock_info)?;
+        let mut delegation_data = delegation_account_info.data.borrow_mut();
+        let state = DelegationState::load_mut(&mut delegation_data)?;
+
+        assert_valid_delegation(
+            state,
+            owner_info.key,
+            delegate_info.key,
+            owner_token_account_info,
+            delegate_token_account_info,
+            mint_info.key,
+            amount,
+        )?;
+        if state.is_expired(clock.unix_timestamp) {
+            return Err(DelegationError::Expired.into());
+        }
+
+        state.check_and_record_spend(operation_type, amount, clock.slot as i64)?;
+        Ok(())
+    }
+
+    // Verifies the delegated action against the same limit/permission rules
+    // as `VerifyTransaction`, then CPIs into the target program so the
+    // delegate's action is actually executed, not just authorized.
+    //
+    // `target_program` is restricted to the SPL token program and `inner_ix`
+    // must decode to a `Transfer`/`TransferChecked` whose `amount` matches
+    // the verified `amount` exactly, so the rate-limit/allowance checks above
+    // actually bind the token movement this CPIs into, not just the
+    // instruction's stated arguments.
+    //
+    // Expected accounts: [owner, delegate, delegation_account, clock, owner_token_account,
+    // delegate_token_account, mint, target_program, ...inner accounts]
+    fn process_execute_delegated(
+        accounts: &[AccountInfo],
+        operation_type: u32,
+        amount: u64,
+        inner_ix: Vec<u8>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner_info = next_account_info(account_info_iter)?;
+        let delegate_info = next_account_info(account_info_iter)?;
+        let delegation_account_info = next_account_info(account_info_iter)?;
+        let clock_info = next_account_info(account_info_iter)?;
+        let owner_token_account_info = next_account_info(account_info_iter)?;
+        let delegate_token_account_info = next_account_info(account_info_iter)?;
+        let mint_info = next_account_info(account_info_iter)?;
+        let target_program_info = next_account_info(account_info_iter)?;
+        let inner_accounts: Vec<&AccountInfo> = account_info_iter.collect();
+
+        if !delegate_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let clock = Clock::from_account_info(clock_info)?;
+        {
+            let mut delegation_data = delegation_account_info.data.borrow_mut();
+            let state = DelegationState::load_mut(&mut delegation_data)?;
+
+            assert_valid_delegation(
+                state,
+                owner_info.key,
+                delegate_info.key,
+                owner_token_account_info,
+                delegate_token_account_info,
+                mint_info.key,
+                amount,
+            )?;
+            if state.is_expired(clock.unix_timestamp) {
+                return Err(DelegationError::Expired.into());
+            }
+
+            state.check_and_record_spend(operation_type, amount, clock.slot as i64)?;
+        }
+
+        if target_program_info.key != &spl_token::id() {
+            return Err(DelegationError::UnsupportedTarget.into());
+        }
+        let inner_amount = match TokenInstruction::unpack(&inner_ix)
+            .map_err(|_| ProgramError::InvalidInstructionData)?
+        {
+            TokenInstruction::Transfer { amount } => amount,
+            TokenInstruction::TransferChecked { amount, .. } => amount,
+            _ => return Err(DelegationError::UnsupportedTarget.into()),
+        };
+        if inner_amount != amount {
+            return Err(DelegationError::AmountMismatch.into());
+        }
+
+        let account_metas = inner_accounts
+            .iter()
+            .map(|info| {
+                if info.is_writable {
+                    AccountMeta::new(*info.key, info.is_signer)
+                } else {
+                    AccountMeta::new_readonly(*info.key, info.is_signer)
+                }
+            })
+            .collect();
+        let inner_instruction = Instruction {
+            program_id: *target_program_info.key,
+            accounts: account_metas,
+            data: inner_ix,
+        };
+        let mut cpi_account_infos: Vec<AccountInfo> =
+            inner_accounts.into_iter().cloned().collect();
+        cpi_account_infos.push(target_program_info.clone());
+        invoke(&inner_instruction, &cpi_account_infos)
+    }
+
+    // Expected accounts: [delegation_account]
+    fn process_get_usage(accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let delegation_account_info = next_account_info(account_info_iter)?;
+
+        let delegation_data = delegation_account_info.data.borrow();
+        let state = DelegationState::load(&delegation_data)?;
+        if !state.is_initialized() {
+            return Err(DelegationError::NotInitialized.into());
+        }
+
+        let usage = UsageInfo {
+            remaining_allowance: state.remaining_allowance(),
+            last_used_slot: state.last_used_slot,
+            expiry_timestamp: state.expiry_timestamp.get(),
+        };
+        let usage_data = usage.try_to_vec().map_err(|_| ProgramError::InvalidAccountData)?;
+        set_return_data(&usage_data);
+        Ok(())
+    }
+}
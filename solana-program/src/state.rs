@@ -0,0 +1,262 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use bytemuck::{Pod, Zeroable};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+use static_assertions::const_assert_eq;
+
+use crate::error::DelegationError;
+
+/// Maximum number of distinct operation types a single delegation can rate-limit.
+pub const MAX_OPERATION_RULES: usize = 8;
+
+const OPTION_NONE: u32 = 0;
+const OPTION_SOME: u32 = 1;
+
+/// A C-compatible, fixed-width stand-in for `Option<T>`: a 4-byte tag
+/// followed by the payload, rather than a sentinel value like `0` or
+/// `i64::MAX`. Lets a delegation mean "no expiry" or "unlimited amount"
+/// unambiguously while keeping the layout `Pod` for zero-copy account data.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct COption<T: Copy + Pod + Zeroable> {
+    tag: u32,
+    _padding: [u8; 4],
+    value: T,
+}
+
+// SAFETY: `tag` and `_padding` are plain integers valid for every bit
+// pattern, and `value: T` is `Pod` by the trait bound, so every bit pattern
+// of `COption<T>` is a valid `COption<T>`. `bytemuck`'s derive macro can't
+// verify this for a generic struct, so the impls are written by hand.
+unsafe impl<T: Copy + Pod + Zeroable> Zeroable for COption<T> {}
+unsafe impl<T: Copy + Pod + Zeroable> Pod for COption<T> {}
+
+impl<T: Copy + Pod + Zeroable> COption<T> {
+    pub fn some(value: T) -> Self {
+        Self { tag: OPTION_SOME, _padding: [0; 4], value }
+    }
+
+    pub fn none() -> Self {
+        Self { tag: OPTION_NONE, _padding: [0; 4], value: T::zeroed() }
+    }
+
+    pub fn get(&self) -> Option<T> {
+        if self.tag == OPTION_SOME {
+            Some(self.value)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Copy + Pod + Zeroable> From<Option<T>> for COption<T> {
+    fn from(opt: Option<T>) -> Self {
+        match opt {
+            Some(value) => Self::some(value),
+            None => Self::none(),
+        }
+    }
+}
+
+/// A sliding-window spending limit for one `operation_type`.
+///
+/// `window_start_slot`/`spent_in_window` form a rolling accumulator: once
+/// `window_slots` slots have elapsed since `window_start_slot`, the window
+/// rolls over and `spent_in_window` resets to zero. Fields are ordered
+/// largest-to-smallest with explicit padding so the struct is safe to
+/// reinterpret directly out of account data.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable, BorshSerialize, BorshDeserialize)]
+pub struct OperationRule {
+    pub max_per_window: u64,
+    pub window_slots: u64,
+    pub window_start_slot: i64,
+    pub spent_in_window: u64,
+    pub operation_type: u32,
+    pub _padding: [u8; 4],
+}
+
+const_assert_eq!(std::mem::size_of::<OperationRule>(), 40);
+
+/// State structure for delegation account.
+///
+/// Laid out `repr(C)` with fields ordered largest-to-smallest and explicit
+/// reserved padding so the struct can be cast directly over account data
+/// (see [`DelegationState::load`]/[`DelegationState::load_mut`]) instead of
+/// borsh-deserialized on every instruction.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct DelegationState {
+    pub owner: Pubkey, // User's wallet
+    pub delegate: Pubkey, // Bot's wallet
+    pub expiry_timestamp: COption<i64>, // None means the delegation never expires
+    pub max_allowed_amount: COption<u64>, // None means no overall cap
+    pub spent_total: u64, // Cumulative amount consumed across all uses
+    pub last_used_slot: i64, // Slot of the most recent successful use
+    pub rules: [OperationRule; MAX_OPERATION_RULES], // Per-operation sliding-window spending caps
+    pub permissions: u32, // Bitmap of allowed operations
+    pub num_rules: u32, // Number of entries in `rules` that are active
+    pub initialized: u8,
+    pub _reserved: [u8; 7],
+}
+
+const_assert_eq!(std::mem::size_of::<DelegationState>(), 448);
+
+impl DelegationState {
+    /// Casts `data` to a `DelegationState` reference without copying or
+    /// allocating.
+    pub fn load(data: &[u8]) -> Result<&Self, ProgramError> {
+        bytemuck::try_from_bytes(data).map_err(|_| ProgramError::InvalidAccountData)
+    }
+
+    /// Casts `data` to a mutable `DelegationState` reference without
+    /// copying or allocating.
+    pub fn load_mut(data: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        bytemuck::try_from_bytes_mut(data).map_err(|_| ProgramError::InvalidAccountData)
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.initialized != 0
+    }
+
+    /// `false` if `expiry_timestamp` is `None` (the delegation never expires).
+    pub fn is_expired(&self, unix_timestamp: i64) -> bool {
+        matches!(self.expiry_timestamp.get(), Some(expiry) if unix_timestamp > expiry)
+    }
+
+    /// Checks `amount` against the overall allowance, the `permissions`
+    /// bitmap, and the sliding-window rule for `operation_type`, rolling
+    /// the window over first if `window_slots` has elapsed. Records the
+    /// spend into the rule's accumulator on success.
+    pub fn check_and_record_spend(
+        &mut self,
+        operation_type: u32,
+        amount: u64,
+        current_slot: i64,
+    ) -> Result<(), DelegationError> {
+        if let Some(max_allowed_amount) = self.max_allowed_amount.get() {
+            if amount > max_allowed_amount {
+                return Err(DelegationError::AmountExceedsAllowance);
+            }
+        }
+        if operation_type >= u32::BITS {
+            return Err(DelegationError::InvalidOperationType);
+        }
+        if self.permissions & (1 << operation_type) == 0 {
+            return Err(DelegationError::OperationNotPermitted);
+        }
+
+        let rule = self.rules[..self.num_rules as usize]
+            .iter_mut()
+            .find(|rule| rule.operation_type == operation_type)
+            .ok_or(DelegationError::NoRuleForOperation)?;
+
+        if current_slot - rule.window_start_slot >= rule.window_slots as i64 {
+            rule.window_start_slot = current_slot;
+            rule.spent_in_window = 0;
+        }
+
+        let spent_in_window = rule
+            .spent_in_window
+            .checked_add(amount)
+            .ok_or(DelegationError::RateLimitExceeded)?;
+        if spent_in_window > rule.max_per_window {
+            return Err(DelegationError::RateLimitExceeded);
+        }
+        rule.spent_in_window = spent_in_window;
+
+        self.spent_total = self.spent_total.saturating_add(amount);
+        self.last_used_slot = current_slot;
+        Ok(())
+    }
+
+    /// Remaining allowance against the overall cap, i.e. what's left after
+    /// `spent_total` is subtracted from `max_allowed_amount`. `None` if the
+    /// delegation has no overall cap.
+    pub fn remaining_allowance(&self) -> Option<u64> {
+        self.max_allowed_amount
+            .get()
+            .map(|max_allowed_amount| max_allowed_amount.saturating_sub(self.spent_total))
+    }
+}
+
+/// Read-only snapshot of a delegation's usage, returned by `GetUsage` via
+/// return data for off-chain monitors to audit.
+#[derive(Debug, BorshSerialize, BorshDeserialize)]
+pub struct UsageInfo {
+    pub remaining_allowance: Option<u64>,
+    pub last_used_slot: i64,
+    pub expiry_timestamp: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPERATION_TYPE: u32 = 1;
+
+    fn state_with_rule(max_per_window: u64, window_slots: u64, window_start_slot: i64, spent_in_window: u64) -> DelegationState {
+        let mut rules = [OperationRule::zeroed(); MAX_OPERATION_RULES];
+        rules[0] = OperationRule {
+            max_per_window,
+            window_slots,
+            window_start_slot,
+            spent_in_window,
+            operation_type: OPERATION_TYPE,
+            _padding: [0; 4],
+        };
+        DelegationState {
+            owner: Pubkey::new_from_array([1; 32]),
+            delegate: Pubkey::new_from_array([2; 32]),
+            expiry_timestamp: COption::none(),
+            max_allowed_amount: COption::some(1_000),
+            spent_total: 0,
+            last_used_slot: 0,
+            rules,
+            permissions: 1 << OPERATION_TYPE,
+            num_rules: 1,
+            initialized: 1,
+            _reserved: [0; 7],
+        }
+    }
+
+    #[test]
+    fn rolls_window_over_once_elapsed() {
+        let mut state = state_with_rule(100, 10, /* window_start_slot */ 0, /* spent_in_window */ 90);
+
+        // current_slot - window_start_slot (15 - 0) >= window_slots (10), so the
+        // window should reset before the new amount is checked.
+        state.check_and_record_spend(OPERATION_TYPE, 60, 15).unwrap();
+
+        let rule = &state.rules[0];
+        assert_eq!(rule.window_start_slot, 15);
+        assert_eq!(rule.spent_in_window, 60);
+    }
+
+    #[test]
+    fn rejects_spend_over_the_window_limit() {
+        let mut state = state_with_rule(100, 10, 0, 50);
+
+        // Still inside the window (5 - 0 < 10), so 50 + 60 must be checked
+        // against the 100 cap and rejected.
+        let err = state.check_and_record_spend(OPERATION_TYPE, 60, 5).unwrap_err();
+        assert_eq!(err, DelegationError::RateLimitExceeded);
+        assert_eq!(state.rules[0].spent_in_window, 50);
+    }
+
+    #[test]
+    fn rejects_unpermitted_operation() {
+        let mut state = state_with_rule(100, 10, 0, 0);
+        state.permissions = 0;
+
+        let err = state.check_and_record_spend(OPERATION_TYPE, 10, 1).unwrap_err();
+        assert_eq!(err, DelegationError::OperationNotPermitted);
+    }
+
+    #[test]
+    fn rejects_operation_type_outside_the_bitmap() {
+        let mut state = state_with_rule(100, 10, 0, 0);
+
+        let err = state.check_and_record_spend(32, 10, 1).unwrap_err();
+        assert_eq!(err, DelegationError::InvalidOperationType);
+    }
+}
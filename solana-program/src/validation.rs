@@ -0,0 +1,195 @@
+use solana_program::{account_info::AccountInfo, program_error::ProgramError, program_pack::Pack, pubkey::Pubkey};
+use spl_token::state::Account as TokenAccount;
+
+use crate::{error::DelegationError, state::DelegationState};
+
+/// Binds a delegation to the specific token accounts and mint an
+/// instruction handler is about to act on, in one place.
+///
+/// Checks that the delegation is initialized, that `owner`/`delegate` match
+/// the stored `DelegationState`, that both token accounts are owned by the
+/// SPL token program and belong to `owner`/`delegate` respectively for
+/// `mint`, and that `amount` does not exceed the delegation's overall
+/// allowance. Every instruction that spends against a delegation should go
+/// through this instead of re-deriving the checks itself.
+pub fn assert_valid_delegation(
+    state: &DelegationState,
+    owner: &Pubkey,
+    delegate: &Pubkey,
+    owner_token_account: &AccountInfo,
+    delegate_token_account: &AccountInfo,
+    mint: &Pubkey,
+    amount: u64,
+) -> Result<(), ProgramError> {
+    if !state.is_initialized() {
+        return Err(DelegationError::NotInitialized.into());
+    }
+    if state.owner != *owner || state.delegate != *delegate {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if let Some(max_allowed_amount) = state.max_allowed_amount.get() {
+        if amount > max_allowed_amount {
+            return Err(DelegationError::AmountExceedsAllowance.into());
+        }
+    }
+
+    if owner_token_account.owner != &spl_token::id() || delegate_token_account.owner != &spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let owner_account = TokenAccount::unpack(&owner_token_account.data.borrow())?;
+    if owner_account.owner != *owner || owner_account.mint != *mint {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let delegate_account = TokenAccount::unpack(&delegate_token_account.data.borrow())?;
+    if delegate_account.owner != *delegate || delegate_account.mint != *mint {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use bytemuck::Zeroable;
+    use solana_program::program_option::COption as SplCOption;
+    use solana_program::program_pack::Pack;
+    use spl_token::state::AccountState;
+
+    use super::*;
+    use crate::state::{COption, OperationRule, MAX_OPERATION_RULES};
+
+    fn initialized_state(owner: Pubkey, delegate: Pubkey) -> DelegationState {
+        DelegationState {
+            owner,
+            delegate,
+            expiry_timestamp: COption::none(),
+            max_allowed_amount: COption::some(1_000),
+            spent_total: 0,
+            last_used_slot: 0,
+            rules: [OperationRule::zeroed(); MAX_OPERATION_RULES],
+            permissions: 0,
+            num_rules: 0,
+            initialized: 1,
+            _reserved: [0; 7],
+        }
+    }
+
+    fn token_account_bytes(owner: Pubkey, mint: Pubkey) -> [u8; TokenAccount::LEN] {
+        let account = TokenAccount {
+            mint,
+            owner,
+            amount: 0,
+            delegate: SplCOption::None,
+            state: AccountState::Initialized,
+            is_native: SplCOption::None,
+            delegated_amount: 0,
+            close_authority: SplCOption::None,
+        };
+        let mut data = [0u8; TokenAccount::LEN];
+        TokenAccount::pack(account, &mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn rejects_token_account_on_the_wrong_mint() {
+        let owner = Pubkey::new_from_array([1; 32]);
+        let delegate = Pubkey::new_from_array([2; 32]);
+        let correct_mint = Pubkey::new_from_array([3; 32]);
+        let wrong_mint = Pubkey::new_from_array([4; 32]);
+        let state = initialized_state(owner, delegate);
+
+        let owner_token_key = Pubkey::new_from_array([5; 32]);
+        let mut owner_token_data = token_account_bytes(owner, wrong_mint);
+        let mut owner_lamports = 0u64;
+        let token_program_id = spl_token::id();
+        let owner_token_account = AccountInfo::new(
+            &owner_token_key,
+            false,
+            false,
+            &mut owner_lamports,
+            &mut owner_token_data,
+            &token_program_id,
+            false,
+            0,
+        );
+
+        let delegate_token_key = Pubkey::new_from_array([6; 32]);
+        let mut delegate_token_data = token_account_bytes(delegate, correct_mint);
+        let mut delegate_lamports = 0u64;
+        let delegate_token_account = AccountInfo::new(
+            &delegate_token_key,
+            false,
+            false,
+            &mut delegate_lamports,
+            &mut delegate_token_data,
+            &token_program_id,
+            false,
+            0,
+        );
+
+        let err = assert_valid_delegation(
+            &state,
+            &owner,
+            &delegate,
+            &owner_token_account,
+            &delegate_token_account,
+            &correct_mint,
+            10,
+        )
+        .unwrap_err();
+        assert_eq!(err, ProgramError::InvalidAccountData);
+    }
+
+    #[test]
+    fn rejects_token_account_belonging_to_the_wrong_wallet() {
+        let owner = Pubkey::new_from_array([1; 32]);
+        let delegate = Pubkey::new_from_array([2; 32]);
+        let someone_else = Pubkey::new_from_array([7; 32]);
+        let mint = Pubkey::new_from_array([3; 32]);
+        let state = initialized_state(owner, delegate);
+
+        let owner_token_key = Pubkey::new_from_array([5; 32]);
+        // Belongs to `someone_else`, not the delegation's `owner`.
+        let mut owner_token_data = token_account_bytes(someone_else, mint);
+        let mut owner_lamports = 0u64;
+        let token_program_id = spl_token::id();
+        let owner_token_account = AccountInfo::new(
+            &owner_token_key,
+            false,
+            false,
+            &mut owner_lamports,
+            &mut owner_token_data,
+            &token_program_id,
+            false,
+            0,
+        );
+
+        let delegate_token_key = Pubkey::new_from_array([6; 32]);
+        let mut delegate_token_data = token_account_bytes(delegate, mint);
+        let mut delegate_lamports = 0u64;
+        let delegate_token_account = AccountInfo::new(
+            &delegate_token_key,
+            false,
+            false,
+            &mut delegate_lamports,
+            &mut delegate_token_data,
+            &token_program_id,
+            false,
+            0,
+        );
+
+        let err = assert_valid_delegation(
+            &state,
+            &owner,
+            &delegate,
+            &owner_token_account,
+            &delegate_token_account,
+            &mint,
+            10,
+        )
+        .unwrap_err();
+        assert_eq!(err, ProgramError::InvalidAccountData);
+    }
+}